@@ -1,3 +1,4 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![doc(html_root_url = "https://docs.rs/try-lazy-init/0.0.2")]
 #![warn(clippy::pedantic)]
 #![allow(clippy::semicolon_if_nothing_returned)]
@@ -10,63 +11,124 @@
 //! 4) Used on multiple threads
 //!
 //! `Lazy<T>` is better than `Mutex<Option<T>>` because after creation accessing
-//! `T` does not require any locking, just a single boolean load with
+//! `T` does not require any locking, just a single state load with
 //! `Ordering::Acquire` (which on x86 is just a compiler barrier, not an actual
 //! memory barrier).
+//!
+//! This crate supports `no_std` via `default-features = false`. Initialization
+//! races are resolved with a small `AtomicU8` state machine instead of a
+//! `Mutex`, so a racing thread waits by calling [`RelaxStrategy::relax`] in a
+//! loop rather than blocking on an OS primitive. [`Spin`] (the default
+//! strategy) busy-waits using
+//! [`core::hint::spin_loop`]; bring your own [`RelaxStrategy`] to yield to a
+//! scheduler instead. Enable the `std` feature to additionally poison an
+//! instance (rather than leave racing threads spinning forever) when a
+//! transforming closure panics.
+
+#[cfg(feature = "std")]
+extern crate std;
 
 #[cfg(doctest)]
 pub mod readme {
 	doc_comment::doctest!("../README.md");
 }
 
-use std::{
+use core::{
 	cell::UnsafeCell,
 	fmt,
-	sync::{
-		atomic::{AtomicBool, Ordering},
-		Mutex,
-	},
+	hint,
+	marker::PhantomData,
+	mem,
+	ops::Deref,
+	sync::atomic::{AtomicU8, Ordering},
 };
 
+const INCOMPLETE: u8 = 0;
+const RUNNING: u8 = 1;
+const COMPLETE: u8 = 2;
+const POISONED: u8 = 3;
+
+/// A strategy for waiting while another thread is in the middle of
+/// initializing a lazily created value.
+///
+/// Implement this to make a racing thread do something other than busy-wait,
+/// such as yielding to an OS scheduler or a cooperative executor. [`Spin`] is
+/// the default and the only strategy this crate provides.
+pub trait RelaxStrategy {
+	/// Called in a loop by a racing thread while it waits for another thread
+	/// to finish an in-progress initialization.
+	fn relax();
+}
+
+/// The default [`RelaxStrategy`]: busy-waits using [`core::hint::spin_loop`].
+///
+/// It needs neither an operating system nor an executor to yield to, so it
+/// is available with or without the `std` feature.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Spin;
+
+impl RelaxStrategy for Spin {
+	fn relax() {
+		hint::spin_loop();
+	}
+}
+
+#[cfg(feature = "std")]
+struct PoisonOnUnwind<'a>(&'a AtomicU8);
+
+#[cfg(feature = "std")]
+impl Drop for PoisonOnUnwind<'_> {
+	fn drop(&mut self) {
+		if std::thread::panicking() {
+			self.0.store(POISONED, Ordering::SeqCst);
+		}
+	}
+}
+
 #[derive(Clone)]
-enum ThisOrThat<T, U> {
-	This(T),
-	That(U),
+enum TransformState<T, U> {
+	Incomplete(T),
+	Running,
+	Complete(U),
+	Poisoned,
 }
 
-/// `LazyTransform<T, U>` is a synchronized holder type, that holds a value of
+/// `LazyTransform<T, U, R>` is a synchronized holder type, that holds a value of
 /// type T until it is lazily converted into a value of type U.
-pub struct LazyTransform<T, U> {
-	initialized: AtomicBool,
-	lock: Mutex<()>,
-	value: UnsafeCell<Option<ThisOrThat<T, U>>>,
+///
+/// `R` is the [`RelaxStrategy`] used by a racing thread while it waits for
+/// another thread's initialization to finish; it defaults to [`Spin`].
+pub struct LazyTransform<T, U, R = Spin> {
+	state: AtomicU8,
+	value: UnsafeCell<TransformState<T, U>>,
+	_relax: PhantomData<R>,
 }
 
 // Implementation details.
-impl<T, U> LazyTransform<T, U> {
+impl<T, U, R> LazyTransform<T, U, R> {
 	fn extract(&self) -> Option<&U> {
-		// Make sure we're initialized first!
-		match unsafe { (*self.value.get()).as_ref() } {
-			None => None,
-			Some(&ThisOrThat::This(_)) => panic!(), // Should already be initialized!
-			Some(&ThisOrThat::That(ref that)) => Some(that),
+		// Make sure we're complete first!
+		match unsafe { &*self.value.get() } {
+			TransformState::Complete(ref u) => Some(u),
+			TransformState::Incomplete(_) | TransformState::Running => None,
+			TransformState::Poisoned => panic!("LazyTransform instance is poisoned"),
 		}
 	}
 }
 
 // Public API.
-impl<T, U> LazyTransform<T, U> {
-	/// Construct a new, untransformed `LazyTransform<T, U>` with an argument of
+impl<T, U, R> LazyTransform<T, U, R> {
+	/// Construct a new, untransformed `LazyTransform<T, U, R>` with an argument of
 	/// type T.
-	pub fn new(t: T) -> LazyTransform<T, U> {
+	pub const fn new(t: T) -> LazyTransform<T, U, R> {
 		LazyTransform {
-			initialized: AtomicBool::new(false),
-			lock: Mutex::new(()),
-			value: UnsafeCell::new(Some(ThisOrThat::This(t))),
+			state: AtomicU8::new(INCOMPLETE),
+			value: UnsafeCell::new(TransformState::Incomplete(t)),
+			_relax: PhantomData,
 		}
 	}
 
-	/// Unwrap the contained value, returning `Ok(U)` if the `LazyTransform<T, U>` has been transformed.
+	/// Unwrap the contained value, returning `Ok(U)` if the `LazyTransform<T, U, R>` has been transformed.
 	///
 	/// # Errors
 	///
@@ -76,15 +138,19 @@ impl<T, U> LazyTransform<T, U> {
 	///
 	/// Iff this instance has been poisoned during transformation.
 	pub fn into_inner(self) -> Result<U, T> {
-		// We don't need to inspect `self.initialized` since `self` is owned
+		// We don't need to inspect `self.state` since `self` is owned
 		// so it is guaranteed that no other threads are accessing its data.
-		match self.value.into_inner().unwrap() {
-			ThisOrThat::This(t) => Err(t),
-			ThisOrThat::That(u) => Ok(u),
+		match self.value.into_inner() {
+			TransformState::Complete(u) => Ok(u),
+			TransformState::Incomplete(t) => Err(t),
+			// `Running` only lingers if a transformation panicked partway through.
+			TransformState::Running | TransformState::Poisoned => {
+				panic!("LazyTransform instance is poisoned")
+			}
 		}
 	}
 
-	/// Unwrap the contained value, returning `Ok(Ok(U))` iff the `LazyTransform<T, U>` has been transformed.
+	/// Unwrap the contained value, returning `Ok(Ok(U))` iff the `LazyTransform<T, U, R>` has been transformed.
 	///
 	/// # Errors
 	///
@@ -96,20 +162,38 @@ impl<T, U> LazyTransform<T, U> {
 	///
 	/// Iff this instance has been poisoned *by a panic* during transformation.
 	pub fn try_into_inner(self) -> Result<U, Option<T>> {
-		// We don't need to inspect `self.initialized` since `self` is owned
+		// We don't need to inspect `self.state` since `self` is owned
 		// so it is guaranteed that no other threads are accessing its data.
 		match self.value.into_inner() {
-			None => Err(None),
-			Some(ThisOrThat::This(t)) => Err(Some(t)),
-			Some(ThisOrThat::That(u)) => Ok(u),
+			TransformState::Complete(u) => Ok(u),
+			TransformState::Incomplete(t) => Err(Some(t)),
+			// `Running` only lingers if a transformation panicked partway through.
+			TransformState::Running => panic!("LazyTransform instance is poisoned"),
+			TransformState::Poisoned => Err(None),
+		}
+	}
+
+	/// Get a reference to the transformed value, returning `Some(&U)` if the
+	/// `LazyTransform<T, U, R>` has been transformed or `None` if it has not.  It
+	/// is guaranteed that if a reference is returned it is to the transformed
+	/// value inside the the `LazyTransform<T, U, R>`.
+	pub fn get(&self) -> Option<&U> {
+		if self.state.load(Ordering::Acquire) == COMPLETE {
+			// We're complete, our value is immutable, no synchronization needed.
+			self.extract()
+		} else {
+			None
 		}
 	}
 }
 
 // Public API.
-impl<T, U> LazyTransform<T, U> {
+impl<T, U, R> LazyTransform<T, U, R>
+where
+	R: RelaxStrategy,
+{
 	/// Get a reference to the transformed value, invoking `f` to transform it
-	/// if the `LazyTransform<T, U>` has yet to be transformed.  It is
+	/// if the `LazyTransform<T, U, R>` has yet to be transformed.  It is
 	/// guaranteed that if multiple calls to `get_or_create` race, only one
 	/// will invoke its closure, and every call will receive a reference to the
 	/// newly transformed value.
@@ -126,35 +210,50 @@ impl<T, U> LazyTransform<T, U> {
 	where
 		F: FnOnce(T) -> U,
 	{
-		// In addition to being correct, this pattern is vouched for by Hans Boehm
-		// (http://schd.ws/hosted_files/cppcon2016/74/HansWeakAtomics.pdf Page 27)
-		if !self.initialized.load(Ordering::Acquire) {
-			// We *may* not be initialized. We have to block to be certain.
-			let _lock = self.lock.lock().unwrap();
-			#[allow(clippy::if_not_else)]
-			if !self.initialized.load(Ordering::Relaxed) {
-				// Ok, we're definitely uninitialized.
-				// Safe to fiddle with the UnsafeCell now, because we're locked,
-				// and there can't be any outstanding references.
-				let value = unsafe { &mut *self.value.get() };
-				let this = match value.take().unwrap() {
-					ThisOrThat::This(t) => t,
-					ThisOrThat::That(_) => panic!(), // Can't already be initialized!
-				};
-				*value = Some(ThisOrThat::That(f(this)));
-				self.initialized.store(true, Ordering::Release);
-			} else {
-				// We raced, and someone else initialized us. We can fall
-				// through now.
+		// `f` only ever runs once, but `wait_while_running` can send us back
+		// around this loop: `state` can legitimately go `RUNNING` -> `INCOMPLETE`
+		// again (a dropped `Setter`, or a failed `.try_get_or_create`), and a
+		// waiter that wakes up to that must re-attempt the CAS rather than
+		// assume it's `COMPLETE`.
+		let mut f = Some(f);
+		loop {
+			if self.state.load(Ordering::Acquire) == COMPLETE {
+				break;
+			}
+
+			// We *may* not be complete. We have to check properly to be certain.
+			match self
+				.state
+				.compare_exchange(INCOMPLETE, RUNNING, Ordering::Acquire, Ordering::Acquire)
+			{
+				Ok(_) => {
+					// We won the race, so we're responsible for the transformation.
+					// Safe to fiddle with the UnsafeCell now, because nobody else
+					// can be holding a reference into it until we publish `Complete`.
+					let value = unsafe { &mut *self.value.get() };
+					let TransformState::Incomplete(this) = mem::replace(value, TransformState::Running) else {
+						panic!() // Can't happen: we just won the race!
+					};
+					#[cfg(feature = "std")]
+					let _poison_on_unwind = PoisonOnUnwind(&self.state);
+					*value = TransformState::Complete(f.take().unwrap()(this));
+					self.state.store(COMPLETE, Ordering::Release);
+					break;
+				}
+				Err(_) => {
+					// We lost the race, or a concurrent `Setter` was dropped
+					// without committing. Wait for things to settle, then retry.
+					self.wait_while_running();
+				}
 			}
 		}
 
-		// We're initialized, our value is immutable, no synchronization needed.
+		// We're complete, our value is immutable, no synchronization needed.
 		self.extract().unwrap()
 	}
 
 	/// Try to get a reference to the transformed value, invoking a fallible `f` to
-	/// transform it if the `LazyTransform<T, U>` has yet to be transformed.
+	/// transform it if the `LazyTransform<T, U, R>` has yet to be transformed.
 	/// It is guaranteed that if multiple calls to `get_or_create` race, only one
 	/// will **successfully** invoke its closure, and every call will receive a
 	/// reference to the newly transformed value.
@@ -176,38 +275,124 @@ impl<T, U> LazyTransform<T, U> {
 		T: Clone,
 		F: FnOnce(T) -> Result<U, E>,
 	{
-		// In addition to being correct, this pattern is vouched for by Hans Boehm
-		// (http://schd.ws/hosted_files/cppcon2016/74/HansWeakAtomics.pdf Page 27)
-		#[allow(clippy::if_not_else)]
-		if !self.initialized.load(Ordering::Acquire) {
-			// We *may* not be initialized. We have to block to be certain.
-			let _lock = self.lock.lock().unwrap();
-			if !self.initialized.load(Ordering::Relaxed) {
-				// Ok, we're definitely uninitialized.
-				// Safe to fiddle with the UnsafeCell now, because we're locked,
-				// and there can't be any outstanding references.
-				//
-				// However, since this function can return early without poisoning this instance,
-				// `self.value` must stay valid until overwritten with `f`'s `Ok`.
-				let value = unsafe { &mut *self.value.get() };
-				let this = match value.as_ref().unwrap() {
-					ThisOrThat::This(t) => t.clone(),
-					ThisOrThat::That(_) => panic!(), // Can't already be initialized!
-				};
-				*value = Some(ThisOrThat::That(f(this)?));
-				self.initialized.store(true, Ordering::Release);
-			} else {
-				// We raced, and someone else initialized us. We can fall
-				// through now.
+		loop {
+			if self.state.load(Ordering::Acquire) == COMPLETE {
+				break;
+			}
+
+			match self
+				.state
+				.compare_exchange(INCOMPLETE, RUNNING, Ordering::Acquire, Ordering::Acquire)
+			{
+				Ok(_) => {
+					// We won the race, so we're responsible for attempting the
+					// transformation. We only clone `T` out (rather than moving
+					// it) so `self.value` stays valid to retry from if `f`
+					// returns `Err`.
+					let this = match unsafe { &*self.value.get() } {
+						TransformState::Incomplete(t) => t.clone(),
+						_ => panic!(), // Can't happen: we just won the race!
+					};
+					#[cfg(feature = "std")]
+					let _poison_on_unwind = PoisonOnUnwind(&self.state);
+					match f(this) {
+						Ok(u) => {
+							unsafe { *self.value.get() = TransformState::Complete(u) };
+							self.state.store(COMPLETE, Ordering::Release);
+							break;
+						}
+						Err(e) => {
+							// Make the instance available for another attempt.
+							self.state.store(INCOMPLETE, Ordering::Release);
+							return Err(e);
+						}
+					}
+				}
+				Err(_) => {
+					// We lost the race, or someone else is retrying. Wait for
+					// them to settle before looking again.
+					self.wait_while_running();
+				}
 			}
 		}
 
-		// We're initialized, our value is immutable, no synchronization needed.
+		// We're complete, our value is immutable, no synchronization needed.
 		Ok(self.extract().unwrap())
 	}
 
+	/// Get a reference to the transformed value, computing it with `f` if the
+	/// `LazyTransform<T, U, R>` has yet to be transformed, without blocking
+	/// on another `.get_or_create_racy` call's `f`.
+	///
+	/// Unlike [`.get_or_create`](`LazyTransform::get_or_create`), a racing
+	/// `.get_or_create_racy` caller never waits for another one's
+	/// (potentially expensive) `f` to finish: `T` is cloned out under a claim
+	/// of `RUNNING` — the same brief exclusive access `Clone` uses — which is
+	/// released again immediately, before `f` runs fully unsynchronized on
+	/// that clone. This means **`f` may run more than once** if callers race
+	/// here, so it must be a pure function of its argument: every caller but
+	/// the one that publishes first throws its `U` away.
+	///
+	/// Racing `.get_or_create_racy` callers only ever spin on each other's
+	/// clone of `T` or publish of `U`, never on `f` itself. A concurrent
+	/// [`.get_or_create`](`LazyTransform::get_or_create`) or [`Setter`] holds
+	/// `RUNNING` for its whole transformation, and this method spins until
+	/// that transformation settles.
+	///
+	/// # Panics
+	///
+	/// Iff this instance has been poisoned during a previous transformation attempt.
+	pub fn get_or_create_racy<F>(&self, f: F) -> &U
+	where
+		T: Clone,
+		F: Fn(&T) -> U,
+	{
+		loop {
+			if self.state.load(Ordering::Acquire) == COMPLETE {
+				break;
+			}
+
+			match self
+				.state
+				.compare_exchange(INCOMPLETE, RUNNING, Ordering::Acquire, Ordering::Acquire)
+			{
+				Ok(_) => {
+					// We only clone `T` out (rather than moving it) and release
+					// `RUNNING` again right away, so a racing
+					// `.get_or_create_racy` call can start the same way instead
+					// of waiting on our (possibly expensive) `f`.
+					let this = match unsafe { &*self.value.get() } {
+						TransformState::Incomplete(t) => t.clone(),
+						_ => panic!(), // Can't happen: we just won the race!
+					};
+					self.state.store(INCOMPLETE, Ordering::Release);
+
+					// The expensive part: computed fully unsynchronized, so it
+					// may run redundantly if callers race here.
+					let u = f(&this);
+
+					if self
+						.state
+						.compare_exchange(INCOMPLETE, RUNNING, Ordering::Acquire, Ordering::Acquire)
+						.is_ok()
+					{
+						unsafe { *self.value.get() = TransformState::Complete(u) };
+						self.state.store(COMPLETE, Ordering::Release);
+						break;
+					}
+					// Someone else published (or is mid-transform) first; `u`
+					// is dropped and we go back around to find out which.
+				}
+				Err(_) => self.wait_while_running(),
+			}
+		}
+
+		// We're complete, our value is immutable, no synchronization needed.
+		self.extract().unwrap()
+	}
+
 	/// Try to get a reference to the transformed value, invoking a fallible `f` to
-	/// transform it if the `LazyTransform<T, U>` has yet to be transformed.
+	/// transform it if the `LazyTransform<T, U, R>` has yet to be transformed.
 	/// It is guaranteed that if multiple calls to `get_or_create` race, only one
 	/// will invoke its closure, and every call will receive a reference to the
 	/// newly transformed value.
@@ -230,125 +415,305 @@ impl<T, U> LazyTransform<T, U> {
 	where
 		F: FnOnce(T) -> Result<U, E>,
 	{
-		// In addition to being correct, this pattern is vouched for by Hans Boehm
-		// (http://schd.ws/hosted_files/cppcon2016/74/HansWeakAtomics.pdf Page 27)
-		#[allow(clippy::if_not_else)]
-		if !self.initialized.load(Ordering::Acquire) {
-			// We *may* not be initialized. We have to block to be certain.
-			let _lock = self.lock.lock().unwrap();
-			if !self.initialized.load(Ordering::Relaxed) {
-				// Ok, we're definitely uninitialized.
-				// Safe to fiddle with the UnsafeCell now, because we're locked,
-				// and there can't be any outstanding references.
-				//
-				// However, since this function can return early without poisoning `self.lock`,
-				// `self.value` is first overwritten with `None` to mark the instance as poisoned-by-error.
-				let value = unsafe { &mut *self.value.get() };
-				let this = match value.take() {
-					None => return Err(None), // Poisoned by previous error.
-					Some(ThisOrThat::This(t)) => t,
-					Some(ThisOrThat::That(_)) => panic!(), // Can't already be initialized!
-				};
-				*value = Some(ThisOrThat::That(f(this)?));
-				self.initialized.store(true, Ordering::Release);
-			} else {
-				// We raced, and someone else initialized us. We can fall
-				// through now.
+		let mut f = Some(f);
+		loop {
+			if self.state.load(Ordering::Acquire) == COMPLETE {
+				break;
+			}
+
+			match self
+				.state
+				.compare_exchange(INCOMPLETE, RUNNING, Ordering::Acquire, Ordering::Acquire)
+			{
+				Ok(_) => {
+					// We won the race, so we're responsible for the transformation.
+					let value = unsafe { &mut *self.value.get() };
+					let TransformState::Incomplete(this) = mem::replace(value, TransformState::Running) else {
+						panic!() // Can't happen: we just won the race!
+					};
+					#[cfg(feature = "std")]
+					let _poison_on_unwind = PoisonOnUnwind(&self.state);
+					match f.take().unwrap()(this) {
+						Ok(u) => {
+							*value = TransformState::Complete(u);
+							self.state.store(COMPLETE, Ordering::Release);
+							break;
+						}
+						Err(e) => {
+							// Poison the instance: there's no value left to retry from.
+							*value = TransformState::Poisoned;
+							self.state.store(POISONED, Ordering::Release);
+							return Err(Some(e));
+						}
+					}
+				}
+				Err(_) => loop {
+					match self.state.load(Ordering::Acquire) {
+						RUNNING => R::relax(),
+						POISONED => {
+							// Distinguish poisoning by error, which leaves `self.value`
+							// set to `Poisoned`, from poisoning by a panic, which
+							// leaves it stuck at `Running` instead.
+							return match unsafe { &*self.value.get() } {
+								TransformState::Poisoned => Err(None),
+								_ => panic!("LazyTransform instance is poisoned"),
+							};
+						}
+						// `COMPLETE`, or `INCOMPLETE` (a concurrent `Setter` from
+						// `.get_or_set_with` was dropped without committing, or a
+						// concurrent `.try_get_or_create` retried after an `Err`):
+						// either the value is ready, or the instance is up for
+						// grabs again. Break out to the outer loop to find out
+						// which and act accordingly.
+						COMPLETE | INCOMPLETE => break,
+						_ => unreachable!(),
+					}
+				},
 			}
 		}
 
-		// We're initialized, our value is immutable, no synchronization needed.
+		// We're complete, our value is immutable, no synchronization needed.
 		Ok(self.extract().unwrap())
 	}
 
-	/// Get a reference to the transformed value, returning `Some(&U)` if the
-	/// `LazyTransform<T, U>` has been transformed or `None` if it has not.  It
-	/// is guaranteed that if a reference is returned it is to the transformed
-	/// value inside the the `LazyTransform<T>`.
-	pub fn get(&self) -> Option<&U> {
-		if self.initialized.load(Ordering::Acquire) {
-			// We're initialized, our value is immutable, no synchronization needed.
-			self.extract()
-		} else {
-			None
+	/// Get a reference to the transformed value, or a [`Setter`] that can be
+	/// used to produce it.
+	///
+	/// Unlike [`.get_or_create`](`LazyTransform::get_or_create`), this splits
+	/// "winning the right to transform" from "producing the transformed
+	/// value" into two steps, so you can run arbitrary code between them
+	/// — including code that borrows from the surrounding scope, branches on
+	/// [`Setter::input`], or returns early — which a single `FnOnce` can't express.
+	///
+	/// # Panics
+	///
+	/// This method will panic if the instance has been poisoned during a previous transformation attempt.
+	///
+	/// The method **may** panic (or deadlock) upon reentrance.
+	pub fn get_or_set_with(&self) -> ValueOrSetter<'_, T, U, R> {
+		loop {
+			if self.state.load(Ordering::Acquire) == COMPLETE {
+				return ValueOrSetter::Value(self.extract().unwrap());
+			}
+
+			match self
+				.state
+				.compare_exchange(INCOMPLETE, RUNNING, Ordering::Acquire, Ordering::Acquire)
+			{
+				Ok(_) => {
+					// We won the race, so we're responsible for the transformation.
+					let TransformState::Incomplete(this) =
+						mem::replace(unsafe { &mut *self.value.get() }, TransformState::Running)
+					else {
+						panic!() // Can't happen: we just won the race!
+					};
+					return ValueOrSetter::Setter(Setter {
+						transform: self,
+						input: Some(this),
+					});
+				}
+				Err(RUNNING) => R::relax(),
+				Err(POISONED) => panic!("LazyTransform instance is poisoned"),
+				// Someone else's `Setter` was dropped without committing: retry.
+				Err(_) => {}
+			}
+		}
+	}
+
+	/// Spin (using `R`) until a racing initialization attempt settles.
+	///
+	/// # Panics
+	///
+	/// Iff the instance is poisoned, by a panic or by an error.
+	fn wait_while_running(&self) {
+		loop {
+			match self.state.load(Ordering::Acquire) {
+				RUNNING => R::relax(),
+				POISONED => panic!("LazyTransform instance is poisoned"),
+				_ => return, // INCOMPLETE (e.g. after a retryable error) or COMPLETE.
+			}
+		}
+	}
+}
+
+// Public API.
+impl<T, U, R> LazyTransform<T, U, R> {
+	/// Get a mutable reference to the transformed value, returning `Some`
+	/// only if the `LazyTransform<T, U, R>` has already been transformed.
+	///
+	/// Since `&mut self` rules out concurrent access, this needs no
+	/// synchronization at all, unlike [`.get`](`LazyTransform::get`).
+	pub fn get_mut(&mut self) -> Option<&mut U> {
+		match self.value.get_mut() {
+			TransformState::Complete(u) => Some(u),
+			TransformState::Incomplete(_) | TransformState::Running | TransformState::Poisoned => {
+				None
+			}
+		}
+	}
+
+	/// Transform in place if necessary, then return a mutable reference to
+	/// the transformed value.
+	///
+	/// Since `&mut self` rules out concurrent access, this needs no
+	/// synchronization at all, unlike [`.get_or_create`](`LazyTransform::get_or_create`).
+	///
+	/// # Panics
+	///
+	/// Iff this instance has been poisoned during a previous transformation attempt.
+	pub fn force_mut<F>(&mut self, f: F) -> &mut U
+	where
+		F: FnOnce(T) -> U,
+	{
+		if !matches!(self.value.get_mut(), TransformState::Complete(_)) {
+			// A panic in `.try_get_or_create` poisons `state` but leaves `T` in place.
+			if *self.state.get_mut() == POISONED {
+				panic!("LazyTransform instance is poisoned")
+			}
+			let this = match mem::replace(self.value.get_mut(), TransformState::Running) {
+				TransformState::Incomplete(t) => t,
+				TransformState::Complete(_) => unreachable!(), // Just checked above.
+				TransformState::Running | TransformState::Poisoned => {
+					panic!("LazyTransform instance is poisoned")
+				}
+			};
+			*self.value.get_mut() = TransformState::Complete(f(this));
+			*self.state.get_mut() = COMPLETE;
+		}
+
+		match self.value.get_mut() {
+			TransformState::Complete(u) => u,
+			_ => unreachable!(), // Just made sure of this!
 		}
 	}
 }
 
-// As `T` is only ever accessed when locked, it's enough if it's `Send` for `Self` to be `Sync`.
-unsafe impl<T, U> Sync for LazyTransform<T, U>
+// Public API.
+impl<T, U, R> LazyTransform<T, U, R>
+where
+	T: Default,
+{
+	/// Reset this instance back to untransformed and return the previously
+	/// transformed value, if any.
+	///
+	/// This does **not** restore the original `T` passed to [`.new`](`LazyTransform::new`)
+	/// or the last [`Setter`] — that value was moved into `f` (or [`Setter::set`])
+	/// when the instance was transformed and is gone. Instead, the instance is
+	/// seeded with a fresh `T::default()`, so a subsequent `get_or_create` (etc.)
+	/// transforms that default, not the original input.
+	///
+	/// This is a deliberately narrower contract than [`Lazy::take`], which
+	/// doesn't need a bound like this one because its consumed "source" is
+	/// `()` (trivially `Default`, and in fact how [`Lazy::take`] gets away
+	/// without asking for `T: Default` on its own `T`). A
+	/// `LazyTransform<T, U, R>` always has *some* `T` sitting in the
+	/// `Incomplete` slot between transformations — there's no "empty" state
+	/// to fall back to — so reseeding it with `T::default()` is the only way
+	/// `.take` can hand back a `U` without also requiring the caller to
+	/// supply a replacement `T` on the spot.
+	///
+	/// Since `&mut self` rules out concurrent access, this needs no
+	/// synchronization at all.
+	pub fn take(&mut self) -> Option<U> {
+		if !matches!(self.value.get_mut(), TransformState::Complete(_)) {
+			return None;
+		}
+
+		let taken = mem::replace(self.value.get_mut(), TransformState::Incomplete(T::default()));
+		*self.state.get_mut() = INCOMPLETE;
+		match taken {
+			TransformState::Complete(u) => Some(u),
+			_ => unreachable!(), // Just checked above.
+		}
+	}
+}
+
+// As `T` is only ever accessed while `state` is claimed, it's enough if it's `Send` for `Self` to be `Sync`.
+unsafe impl<T, U, R> Sync for LazyTransform<T, U, R>
 where
 	T: Send,
 	U: Send + Sync,
 {
 }
 
-impl<T, U> Clone for LazyTransform<T, U>
+impl<T, U, R> Clone for LazyTransform<T, U, R>
 where
 	T: Clone,
 	U: Clone,
+	R: RelaxStrategy,
 {
 	fn clone(&self) -> Self {
 		// Overall, this method is very similar to `get_or_create` and uses the same
-		// soundness reasoning.
-
-		if self.initialized.load(Ordering::Acquire) {
-			Self {
-				initialized: true.into(),
-				lock: Mutex::default(),
-				value: UnsafeCell::new(unsafe {
-					// SAFETY:
-					// Everything is initialized and immutable here, so lockless cloning is safe.
-					(&*self.value.get()).clone()
-				}),
-			}
-		} else {
-			// We *may* not be initialized. We have to block here before accessing `value`,
-			// which also synchronises the `initialized` load.
-			let _lock = self.lock.lock().unwrap();
-			Self {
-				initialized: self.initialized.load(Ordering::Relaxed).into(),
-				lock: Mutex::default(),
-				value: UnsafeCell::new(unsafe {
-					// SAFETY:
-					// Exclusive access while `_lock` is held.
-					(&*self.value.get()).clone()
-				}),
+		// soundness reasoning: we claim `RUNNING` ourselves (rather than just
+		// observing `INCOMPLETE`) so that a concurrent initialization can't start
+		// mutating `self.value` while we're reading it.
+		loop {
+			match self
+				.state
+				.compare_exchange(INCOMPLETE, RUNNING, Ordering::Acquire, Ordering::Acquire)
+			{
+				Ok(_) => {
+					let cloned = unsafe {
+						// SAFETY: We hold exclusive access, having just claimed `RUNNING`.
+						(&*self.value.get()).clone()
+					};
+					self.state.store(INCOMPLETE, Ordering::Release);
+					return Self {
+						state: AtomicU8::new(INCOMPLETE),
+						value: UnsafeCell::new(cloned),
+						_relax: PhantomData,
+					};
+				}
+				Err(COMPLETE) => {
+					return Self {
+						state: AtomicU8::new(COMPLETE),
+						value: UnsafeCell::new(unsafe {
+							// SAFETY: Complete and immutable, so lockless cloning is safe.
+							(&*self.value.get()).clone()
+						}),
+						_relax: PhantomData,
+					};
+				}
+				Err(POISONED) => panic!("LazyTransform instance is poisoned"),
+				Err(_) => R::relax(), // RUNNING: wait for the race to settle, then retry.
 			}
 		}
 	}
 
 	fn clone_from(&mut self, source: &Self) {
-		// Overall, this method is very similar to `get_or_create` and uses the same
-		// soundness reasoning. It's implemented explicitly here to avoid a `Mutex` drop/new.
-
-		if self.initialized.load(Ordering::Acquire) {
-			unsafe {
-				// SAFETY:
-				// Everything is initialized and immutable here, so lockless cloning is safe.
-				// It's still important to store `initialized` with correct ordering, though.
-				*self.value.get() = (&*source.value.get()).clone();
-				self.initialized.store(true, Ordering::Release);
-			}
-		} else {
-			// `source` *may* not be initialized. We have to block here before accessing `value`,
-			// which also synchronises the `initialized` load (and incidentally also the `initialized`
-			// store due to the exclusive reference to `self`, so that can be `Relaxed` here too).
-			let _lock = source.lock.lock().unwrap();
-			unsafe {
-				// SAFETY:
-				// Exclusive access to `source` while `_lock` is held.
-				*self.value.get() = (&*source.value.get()).clone();
-				self.initialized.store(
-					source.initialized.load(Ordering::Relaxed),
-					Ordering::Relaxed,
-				);
+		// Overall, this method is very similar to `clone` and uses the same
+		// soundness reasoning. It's implemented explicitly here to avoid
+		// allocating a new instance just to move out of it.
+		loop {
+			match source
+				.state
+				.compare_exchange(INCOMPLETE, RUNNING, Ordering::Acquire, Ordering::Acquire)
+			{
+				Ok(_) => {
+					unsafe {
+						// SAFETY: Exclusive access to `source`, having just claimed `RUNNING`.
+						*self.value.get() = (&*source.value.get()).clone();
+					}
+					self.state.store(INCOMPLETE, Ordering::Relaxed);
+					source.state.store(INCOMPLETE, Ordering::Release);
+					return;
+				}
+				Err(COMPLETE) => {
+					unsafe {
+						// SAFETY: Complete and immutable, so lockless cloning is safe.
+						*self.value.get() = (&*source.value.get()).clone();
+					}
+					self.state.store(COMPLETE, Ordering::Relaxed);
+					return;
+				}
+				Err(POISONED) => panic!("LazyTransform instance is poisoned"),
+				Err(_) => R::relax(),
 			}
 		}
 	}
 }
 
-impl<T, U> Default for LazyTransform<T, U>
+impl<T, U, R> Default for LazyTransform<T, U, R>
 where
 	T: Default,
 {
@@ -357,48 +722,176 @@ where
 	}
 }
 
-/// `Lazy<T>` is a lazily initialized synchronized holder type.  You can think
+/// Returned by [`LazyTransform::get_or_set_with`]: either the value already
+/// transformed by a previous call, or a [`Setter`] handle to produce it.
+pub enum ValueOrSetter<'a, T, U, R = Spin> {
+	/// The `LazyTransform<T, U, R>` was already transformed.
+	Value(&'a U),
+	/// The `LazyTransform<T, U, R>` was not transformed yet, and this call won
+	/// the right to transform it.
+	Setter(Setter<'a, T, U, R>),
+}
+
+/// An exclusive handle to transform a [`LazyTransform<T, U, R>`], obtained
+/// from [`LazyTransform::get_or_set_with`].
+///
+/// Dropping a `Setter` without calling [`.set`](`Setter::set`) or
+/// [`.try_set`](`Setter::try_set`) — including by panicking — gives the held
+/// `T` back to the `LazyTransform<T, U, R>`, making it available for another
+/// attempt.
+pub struct Setter<'a, T, U, R = Spin> {
+	transform: &'a LazyTransform<T, U, R>,
+	input: Option<T>,
+}
+
+impl<'a, T, U, R> Setter<'a, T, U, R> {
+	/// The value to transform.
+	///
+	/// # Panics
+	///
+	/// Never: `self.input` is only taken by `.set`/`.try_set`, which consume `self`.
+	pub fn input(&self) -> &T {
+		self.input.as_ref().unwrap() // Always `Some` until `.set`/`.try_set` consumes it.
+	}
+
+	/// Install `u` as the transformed value and return a reference to it.
+	///
+	/// # Panics
+	///
+	/// Never: `self` having existed at all means the `LazyTransform<T, U, R>`
+	/// was successfully claimed for transformation, so `.extract` always
+	/// succeeds once `u` has just been published.
+	pub fn set(mut self, u: U) -> &'a U {
+		self.input = None; // Disarm `Drop`: we're committing, not cancelling.
+		unsafe { *self.transform.value.get() = TransformState::Complete(u) };
+		self.transform.state.store(COMPLETE, Ordering::Release);
+		self.transform.extract().unwrap()
+	}
+
+	/// Like [`.set`](`Setter::set`), but for a fallible transformation: iff
+	/// `result` is [`Err`], the held `T` is given back to the
+	/// `LazyTransform<T, U, R>` instead, making it available for another attempt.
+	///
+	/// # Errors
+	///
+	/// Iff `result` is [`Result::Err`], this error is returned verbatim.
+	pub fn try_set<E>(self, result: Result<U, E>) -> Result<&'a U, E> {
+		result.map(|u| self.set(u))
+	}
+}
+
+impl<T, U, R> Drop for Setter<'_, T, U, R> {
+	fn drop(&mut self) {
+		if let Some(t) = self.input.take() {
+			unsafe { *self.transform.value.get() = TransformState::Incomplete(t) };
+			self.transform.state.store(INCOMPLETE, Ordering::Release);
+		}
+	}
+}
+
+/// `Lazy<T, R>` is a lazily initialized synchronized holder type.  You can think
 /// of it as a `LazyTransform` where the initial type doesn't exist.
-#[derive(Clone)]
-pub struct Lazy<T> {
-	inner: LazyTransform<(), T>,
+///
+/// `R` is the [`RelaxStrategy`] used by a racing thread while it waits for
+/// another thread's initialization to finish; it defaults to [`Spin`].
+pub struct Lazy<T, R = Spin> {
+	inner: LazyTransform<(), T, R>,
 }
 
-impl<T> Lazy<T> {
-	/// Construct a new, uninitialized `Lazy<T>`.
+// `#[derive(Clone)]` would add an unnecessary `R: Clone` bound instead of the
+// `R: RelaxStrategy` bound `LazyTransform::clone` actually needs.
+impl<T, R> Clone for Lazy<T, R>
+where
+	T: Clone,
+	R: RelaxStrategy,
+{
+	fn clone(&self) -> Self {
+		Lazy {
+			inner: self.inner.clone(),
+		}
+	}
+
+	fn clone_from(&mut self, source: &Self) {
+		self.inner.clone_from(&source.inner);
+	}
+}
+
+impl<T, R> Lazy<T, R> {
+	/// Construct a new, uninitialized `Lazy<T, R>`.
 	#[must_use]
-	pub fn new() -> Lazy<T> {
+	pub fn new() -> Lazy<T, R> {
 		Self::default()
 	}
 
-	/// Unwrap the contained value, returning `Some` if the `Lazy<T>` has been initialized
+	/// Unwrap the contained value, returning `Some` if the `Lazy<T, R>` has been initialized
 	/// or `None` if it has not.
 	pub fn into_inner(self) -> Option<T> {
 		self.inner.into_inner().ok()
 	}
+
+	/// Get a reference to the contained value, returning `Some(ref)` if the
+	/// `Lazy<T, R>` has been initialized or `None` if it has not.  It is
+	/// guaranteed that if a reference is returned it is to the value inside
+	/// the `Lazy<T, R>`.
+	pub fn get(&self) -> Option<&T> {
+		self.inner.get()
+	}
+
+	/// Get a mutable reference to the contained value, returning `Some` only
+	/// if the `Lazy<T, R>` has already been initialized.
+	///
+	/// Since `&mut self` rules out concurrent access, this needs no
+	/// synchronization at all, unlike [`.get`](`Lazy::get`).
+	pub fn get_mut(&mut self) -> Option<&mut T> {
+		self.inner.get_mut()
+	}
+
+	/// Initialize in place if necessary, then return a mutable reference to
+	/// the contained value.
+	///
+	/// Since `&mut self` rules out concurrent access, this needs no
+	/// synchronization at all, unlike [`.get_or_create`](`Lazy::get_or_create`).
+	pub fn force_mut<F>(&mut self, f: F) -> &mut T
+	where
+		F: FnOnce() -> T,
+	{
+		self.inner.force_mut(|()| f())
+	}
+
+	/// Reset this instance back to uninitialized, returning the previously
+	/// contained value, if any.
+	///
+	/// Since `&mut self` rules out concurrent access, this needs no
+	/// synchronization at all.
+	pub fn take(&mut self) -> Option<T> {
+		self.inner.take()
+	}
 }
 
-impl<T> Lazy<T> {
+impl<T, R> Lazy<T, R>
+where
+	R: RelaxStrategy,
+{
 	/// Get a reference to the contained value, invoking `f` to create it
-	/// if the `Lazy<T>` is uninitialized.  It is guaranteed that if multiple
+	/// if the `Lazy<T, R>` is uninitialized.  It is guaranteed that if multiple
 	/// calls to `get_or_create` race, only one will invoke its closure, and
 	/// every call will receive a reference to the newly created value.
 	///
-	/// The value stored in the `Lazy<T>` is immutable after the closure returns
+	/// The value stored in the `Lazy<T, R>` is immutable after the closure returns
 	/// it, so think carefully about what you want to put inside!
 	pub fn get_or_create<F>(&self, f: F) -> &T
 	where
 		F: FnOnce() -> T,
 	{
-		self.inner.get_or_create(|_| f())
+		self.inner.get_or_create(|()| f())
 	}
 
 	/// Tries to get a reference to the contained value, invoking `f` to create it
-	/// if the `Lazy<T>` is uninitialized.  It is guaranteed that if multiple
+	/// if the `Lazy<T, R>` is uninitialized.  It is guaranteed that if multiple
 	/// calls to `get_or_create` race, only one will **successfully** invoke its
 	/// closure, and every call will receive a reference to the newly created value.
 	///
-	/// The value stored in the `Lazy<T>` is immutable after the closure succeeds
+	/// The value stored in the `Lazy<T, R>` is immutable after the closure succeeds
 	/// and returns it, so think carefully about what you want to put inside!
 	///
 	/// # Errors
@@ -408,21 +901,21 @@ impl<T> Lazy<T> {
 	where
 		F: FnOnce() -> Result<T, E>,
 	{
-		self.inner.try_get_or_create(|_| f())
+		self.inner.try_get_or_create(|()| f())
 	}
 
-	/// Get a reference to the contained value, returning `Some(ref)` if the
-	/// `Lazy<T>` has been initialized or `None` if it has not.  It is
-	/// guaranteed that if a reference is returned it is to the value inside
-	/// the `Lazy<T>`.
-	pub fn get(&self) -> Option<&T> {
-		self.inner.get()
+	/// Get a reference to the contained value, or a [`Setter`] that can be
+	/// used to produce it.
+	///
+	/// See [`LazyTransform::get_or_set_with`] for details.
+	pub fn get_or_set_with(&self) -> ValueOrSetter<'_, (), T, R> {
+		self.inner.get_or_set_with()
 	}
 }
 
 // `#[derive(Default)]` automatically adds `T: Default` trait bound, but that
-// is too restrictive, because `Lazy<T>` always has a default value for any `T`.
-impl<T> Default for Lazy<T> {
+// is too restrictive, because `Lazy<T, R>` always has a default value for any `T`.
+impl<T, R> Default for Lazy<T, R> {
 	fn default() -> Self {
 		Lazy {
 			inner: LazyTransform::new(()),
@@ -430,26 +923,221 @@ impl<T> Default for Lazy<T> {
 	}
 }
 
-impl<T> fmt::Debug for Lazy<T>
+impl<T, R> fmt::Debug for Lazy<T, R>
 where
 	T: fmt::Debug,
 {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		if let Some(v) = self.get() {
-			f.write_fmt(format_args!("Lazy({:?})", v))
+			f.write_fmt(format_args!("Lazy({v:?})"))
 		} else {
 			f.write_str("Lazy(<uninitialized>)")
 		}
 	}
 }
 
-#[cfg(test)]
+enum LazyLockState<T, F> {
+	Uninit(F),
+	Running,
+	Init(T),
+}
+
+/// `LazyLock<T, F = fn() -> T, R = Spin>` is a lazily initialized synchronized holder
+/// type that owns its initializing closure, so that, unlike [`Lazy<T, R>`],
+/// it can be constructed directly in a `static` without a helper function.
+///
+/// Once forced, `LazyLock<T, F, R>` derefs to `T` without any locking, just
+/// like the other types in this crate. `R` is the [`RelaxStrategy`] used by a
+/// racing thread while it waits for another thread's initialization to
+/// finish; it defaults to [`Spin`].
+pub struct LazyLock<T, F = fn() -> T, R = Spin> {
+	state: AtomicU8,
+	value: UnsafeCell<LazyLockState<T, F>>,
+	_relax: PhantomData<R>,
+}
+
+impl<T, F, R> LazyLock<T, F, R> {
+	/// Construct a new, uninitialized `LazyLock<T, F, R>` that will call `f` to
+	/// produce its value the first time it is forced.
+	///
+	/// This is a `const fn`, so it can be used to initialize a `static`.
+	#[must_use]
+	pub const fn new(f: F) -> LazyLock<T, F, R> {
+		LazyLock {
+			state: AtomicU8::new(INCOMPLETE),
+			value: UnsafeCell::new(LazyLockState::Uninit(f)),
+			_relax: PhantomData,
+		}
+	}
+
+	/// Get a mutable reference to the contained value, returning `Some` only
+	/// if the `LazyLock<T, F, R>` has already been forced.
+	///
+	/// Since `&mut self` rules out concurrent access, this needs no
+	/// synchronization at all, unlike [`.force`](`LazyLock::force`).
+	pub fn get_mut(&mut self) -> Option<&mut T> {
+		match self.value.get_mut() {
+			LazyLockState::Init(t) => Some(t),
+			LazyLockState::Uninit(_) | LazyLockState::Running => None,
+		}
+	}
+
+	fn peek(&self) -> Option<&T> {
+		if self.state.load(Ordering::Acquire) == COMPLETE {
+			// We're complete, our value is immutable, no synchronization needed.
+			match unsafe { &*self.value.get() } {
+				LazyLockState::Init(ref t) => Some(t),
+				_ => panic!(), // Should already be complete!
+			}
+		} else {
+			None
+		}
+	}
+}
+
+impl<T, F, R> LazyLock<T, F, R>
+where
+	F: FnOnce() -> T,
+	R: RelaxStrategy,
+{
+	/// Force evaluation of this `LazyLock<T, F, R>` and return a reference to
+	/// the result. It is guaranteed that if multiple calls to `force` race,
+	/// only one will invoke `f`, and every call will receive a reference to
+	/// the newly created value.
+	///
+	/// # Panics
+	///
+	/// This method will panic if the instance has been poisoned by a panic
+	/// during a previous forcing attempt.
+	///
+	/// The method **may** panic (or deadlock) upon reentrance.
+	pub fn force(&self) -> &T {
+		if self.state.load(Ordering::Acquire) != COMPLETE {
+			// We *may* not be complete. We have to check properly to be certain.
+			match self
+				.state
+				.compare_exchange(INCOMPLETE, RUNNING, Ordering::Acquire, Ordering::Acquire)
+			{
+				Ok(_) => {
+					// We won the race, so we're responsible for the forcing.
+					let value = unsafe { &mut *self.value.get() };
+					let LazyLockState::Uninit(f) = mem::replace(value, LazyLockState::Running) else {
+						panic!() // Can't happen: we just won the race!
+					};
+					#[cfg(feature = "std")]
+					let _poison_on_unwind = PoisonOnUnwind(&self.state);
+					*value = LazyLockState::Init(f());
+					self.state.store(COMPLETE, Ordering::Release);
+				}
+				Err(_) => {
+					// We lost the race. Wait for the winner to publish their result.
+					loop {
+						match self.state.load(Ordering::Acquire) {
+							COMPLETE => break,
+							POISONED => panic!("LazyLock instance is poisoned"),
+							_ => R::relax(),
+						}
+					}
+				}
+			}
+		}
+
+		// We're complete, our value is immutable, no synchronization needed.
+		match unsafe { &*self.value.get() } {
+			LazyLockState::Init(ref t) => t,
+			_ => panic!(), // Just made sure of this!
+		}
+	}
+
+	/// Unwrap the contained value, returning `Ok(T)` if the `LazyLock<T, F, R>`
+	/// has been forced, or `Err(F)` with the stored closure if it has not.
+	///
+	/// # Errors
+	///
+	/// Iff this instance has not been forced yet, `Err` with the stored closure is returned.
+	///
+	/// # Panics
+	///
+	/// Iff this instance has been poisoned during a previous forcing attempt.
+	pub fn into_inner(self) -> Result<T, F> {
+		// We don't need to inspect `self.state` since `self` is owned
+		// so it is guaranteed that no other threads are accessing its data.
+		match self.value.into_inner() {
+			LazyLockState::Init(t) => Ok(t),
+			LazyLockState::Uninit(f) => Err(f),
+			// `Running` only lingers if a previous forcing attempt panicked partway through.
+			LazyLockState::Running => panic!("LazyLock instance is poisoned"),
+		}
+	}
+
+	/// Force evaluation if necessary, then return a mutable reference to the
+	/// result.
+	///
+	/// Since `&mut self` rules out concurrent access, this needs no
+	/// synchronization at all, unlike [`.force`](`LazyLock::force`).
+	///
+	/// # Panics
+	///
+	/// This method will panic if the instance has been poisoned by a panic
+	/// during a previous forcing attempt.
+	pub fn force_mut(&mut self) -> &mut T {
+		if !matches!(self.value.get_mut(), LazyLockState::Init(_)) {
+			let f = match mem::replace(self.value.get_mut(), LazyLockState::Running) {
+				LazyLockState::Uninit(f) => f,
+				LazyLockState::Init(_) => unreachable!(), // Just checked above.
+				LazyLockState::Running => panic!("LazyLock instance is poisoned"),
+			};
+			*self.value.get_mut() = LazyLockState::Init(f());
+			*self.state.get_mut() = COMPLETE;
+		}
+
+		match self.value.get_mut() {
+			LazyLockState::Init(t) => t,
+			_ => unreachable!(), // Just made sure of this!
+		}
+	}
+}
+
+impl<T, F, R> Deref for LazyLock<T, F, R>
+where
+	F: FnOnce() -> T,
+	R: RelaxStrategy,
+{
+	type Target = T;
+
+	fn deref(&self) -> &T {
+		self.force()
+	}
+}
+
+// As `F` is only ever called while `state` is claimed, it's enough if it's `Send` for `Self` to be `Sync`.
+unsafe impl<T, F, R> Sync for LazyLock<T, F, R>
+where
+	T: Send + Sync,
+	F: Send,
+{
+}
+
+impl<T, F, R> fmt::Debug for LazyLock<T, F, R>
+where
+	T: fmt::Debug,
+{
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if let Some(v) = self.peek() {
+			f.write_fmt(format_args!("LazyLock({v:?})"))
+		} else {
+			f.write_str("LazyLock(<uninitialized>)")
+		}
+	}
+}
+
+#[cfg(all(test, feature = "std"))]
 extern crate scoped_pool;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
 
-	use super::{Lazy, LazyTransform};
+	use super::{Lazy, LazyLock, LazyTransform, ValueOrSetter};
 	use scoped_pool::Pool;
 	use std::{
 		sync::atomic::{AtomicUsize, Ordering},
@@ -613,4 +1301,139 @@ mod tests {
 
 		assert_eq!(n.load(Ordering::SeqCst), 1);
 	}
+
+	#[test]
+	fn test_lazy_transform_racy() {
+		let lazy_value: LazyTransform<u8, u8> = LazyTransform::new(21);
+
+		assert_eq!(lazy_value.get(), None);
+
+		let n = AtomicUsize::new(0);
+
+		let pool = Pool::new(100);
+		pool.scoped(|scope| {
+			for _ in 0..100 {
+				let lazy_ref = &lazy_value;
+				let n_ref = &n;
+				scope.execute(move || {
+					let value = *lazy_ref.get_or_create_racy(|v| {
+						// Unlike `get_or_create`, this may run more than once.
+						n_ref.fetch_add(1, Ordering::Relaxed);
+
+						v * 2
+					});
+					assert_eq!(value, 42);
+
+					let value = lazy_ref.get();
+					assert_eq!(value, Some(&42));
+				});
+			}
+		});
+
+		assert!(n.load(Ordering::SeqCst) >= 1);
+	}
+
+	#[test]
+	fn test_lazy_lock() {
+		let n = AtomicUsize::new(0);
+		let lazy_value: LazyLock<u8, _> = LazyLock::new(|| {
+			n.fetch_add(1, Ordering::Relaxed);
+			42
+		});
+
+		let pool = Pool::new(100);
+		pool.scoped(|scope| {
+			for _ in 0..100 {
+				let lazy_ref = &lazy_value;
+				scope.execute(move || {
+					let ten_millis = time::Duration::from_millis(10);
+					thread::sleep(ten_millis);
+
+					assert_eq!(**lazy_ref, 42);
+					assert_eq!(*lazy_ref.force(), 42);
+				});
+			}
+		});
+
+		assert_eq!(n.load(Ordering::SeqCst), 1);
+		assert_eq!(lazy_value.into_inner().ok().unwrap(), 42);
+	}
+
+	#[test]
+	fn test_mut_access() {
+		let mut lazy_value: Lazy<u8> = Lazy::new();
+		assert_eq!(lazy_value.get_mut(), None);
+
+		assert_eq!(*lazy_value.force_mut(|| 42), 42);
+		*lazy_value.get_mut().unwrap() += 1;
+		assert_eq!(lazy_value.get(), Some(&43));
+
+		assert_eq!(lazy_value.take(), Some(43));
+		assert_eq!(lazy_value.get(), None);
+
+		let mut transform_value: LazyTransform<u8, u8> = LazyTransform::new(21);
+		assert_eq!(transform_value.get_mut(), None);
+
+		assert_eq!(*transform_value.force_mut(|v| v * 2), 42);
+		*transform_value.get_mut().unwrap() += 1;
+		assert_eq!(transform_value.get(), Some(&43));
+
+		assert_eq!(transform_value.take(), Some(43));
+		assert_eq!(transform_value.get(), None);
+
+		let mut lazy_lock_value: LazyLock<u8, _> = LazyLock::new(|| 42);
+		assert_eq!(lazy_lock_value.get_mut(), None);
+
+		assert_eq!(*lazy_lock_value.force_mut(), 42);
+		*lazy_lock_value.get_mut().unwrap() += 1;
+		assert_eq!(*lazy_lock_value, 43);
+	}
+
+	#[test]
+	fn test_setter() {
+		let lazy_value: LazyTransform<u8, u8> = LazyTransform::new(21);
+
+		// A dropped `Setter` gives the input back, ready for another attempt.
+		match lazy_value.get_or_set_with() {
+			ValueOrSetter::Value(_) => panic!("expected a Setter"),
+			ValueOrSetter::Setter(setter) => assert_eq!(*setter.input(), 21),
+		}
+		assert_eq!(lazy_value.get(), None);
+
+		let n = AtomicUsize::new(0);
+
+		let pool = Pool::new(100);
+		pool.scoped(|scope| {
+			for _ in 0..100 {
+				let lazy_ref = &lazy_value;
+				let n_ref = &n;
+				scope.execute(move || {
+					let ten_millis = time::Duration::from_millis(10);
+					thread::sleep(ten_millis);
+
+					let value = match lazy_ref.get_or_set_with() {
+						ValueOrSetter::Value(v) => v,
+						ValueOrSetter::Setter(setter) => {
+							let input = *setter.input();
+
+							// Make everybody else wait on me, because I'm a jerk.
+							thread::sleep(ten_millis);
+
+							// Make this relaxed so it doesn't interfere with
+							// Lazy internals at all.
+							n_ref.fetch_add(1, Ordering::Relaxed);
+
+							setter.set(input * 2)
+						}
+					};
+					assert_eq!(*value, 42);
+
+					let value = lazy_ref.get();
+					assert_eq!(value, Some(&42));
+				});
+			}
+		});
+
+		assert_eq!(n.load(Ordering::SeqCst), 1);
+	}
 }